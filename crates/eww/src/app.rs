@@ -75,6 +75,21 @@ pub enum DaemonCommand {
     PrintDebug(DaemonResponseSender),
     PrintGraph(DaemonResponseSender),
     PrintWindows(DaemonResponseSender),
+    PrintProfile(DaemonResponseSender),
+    PrintStats(DaemonResponseSender),
+    StoreSet {
+        key: String,
+        value: DynVal,
+        sender: DaemonResponseSender,
+    },
+    StoreGet {
+        key: String,
+        sender: DaemonResponseSender,
+    },
+    StoreDel {
+        key: String,
+        sender: DaemonResponseSender,
+    },
 }
 
 /// An opened window.
@@ -84,6 +99,11 @@ pub struct EwwWindow {
     pub scope_index: ScopeIndex,
     pub gtk_window: gtk::Window,
     pub destroy_event_handler_id: Option<glib::SignalHandlerId>,
+    /// Handler this window connected to the `configure-event` of the window it is positioned
+    /// relative to (see `:relative-to`), together with a reference to that target window.
+    pub attach_event_handler: Option<(glib::WeakRef<gtk::Window>, glib::SignalHandlerId)>,
+    /// Handler a wallpaper window connected to the `size-changed` event of its screen.
+    pub screen_size_handler: Option<(gdk::Screen, glib::SignalHandlerId)>,
 }
 
 impl EwwWindow {
@@ -97,6 +117,20 @@ impl EwwWindow {
         if let Some(handler_id) = self.destroy_event_handler_id {
             self.gtk_window.disconnect(handler_id);
         }
+        // disconnect the handler this window connected to its :relative-to target,
+        // so closing and reopening this window doesn't pile up handlers on the target
+        if let Some((target_window, handler_id)) = self.attach_event_handler {
+            if let Some(target_window) = target_window.upgrade() {
+                target_window.disconnect(handler_id);
+            }
+        }
+        // the screen outlives the window, so the handler needs to be disconnected explicitly
+        if let Some((screen, handler_id)) = self.screen_size_handler {
+            screen.disconnect(handler_id);
+        }
+        // `close` only hides the window, so explicitly destroy it to tear down
+        // the entire widget tree and the signal handlers attached to it.
+        unsafe { self.gtk_window.destroy() };
     }
 }
 
@@ -109,6 +143,8 @@ pub struct App<B> {
     /// Window names that are supposed to be open, but failed.
     /// When reloading the config, these should be opened again.
     pub failed_windows: HashSet<String>,
+    /// Key-value store for transient script data, managed via `eww store`
+    pub store: HashMap<String, DynVal>,
     pub css_provider: gtk::CssProvider,
 
     /// Sender to send [`DaemonCommand`]s
@@ -137,6 +173,9 @@ impl<B: DisplayBackend> App<B> {
     /// Handle a [`DaemonCommand`] event.
     pub fn handle_command(&mut self, event: DaemonCommand) {
         log::debug!("Handling event: {:?}", &event);
+        if crate::tracer::is_active() {
+            crate::tracer::emit(format!("handling command: {:?}", &event));
+        }
         let result: Result<_> = try {
             match event {
                 DaemonCommand::NoOp => {}
@@ -250,6 +289,35 @@ impl<B: DisplayBackend> App<B> {
                     sender.send_success(output)?
                 }
                 DaemonCommand::PrintGraph(sender) => sender.send_success(self.scope_graph.borrow().visualize())?,
+                DaemonCommand::PrintProfile(sender) => sender.send_success(crate::profiler::take_report())?,
+                DaemonCommand::PrintStats(sender) => {
+                    let (resident, virtual_mem) = crate::config::system_stats::get_daemon_memory_usage()?;
+                    let scope_graph = self.scope_graph.borrow();
+                    let output = format!(
+                        "open windows: {}\nscopes: {}\nglobal variables: {}\nmemory usage: {:.2} MB resident, {:.2} MB \
+                         virtual",
+                        self.open_windows.len(),
+                        scope_graph.scope_count(),
+                        scope_graph.global_scope().data.len(),
+                        resident as f64 / 1e6,
+                        virtual_mem as f64 / 1e6,
+                    );
+                    sender.send_success(output)?
+                }
+                DaemonCommand::StoreSet { key, value, sender } => {
+                    self.store.insert(key, value);
+                    self.update_store_variable();
+                    sender.send_success(String::new())?
+                }
+                DaemonCommand::StoreGet { key, sender } => match self.store.get(&key) {
+                    Some(value) => sender.send_success(value.to_string())?,
+                    None => sender.send_failure(format!("No value stored under key \"{}\"", key))?,
+                },
+                DaemonCommand::StoreDel { key, sender } => {
+                    self.store.remove(&key);
+                    self.update_store_variable();
+                    sender.send_success(String::new())?
+                }
             }
         };
 
@@ -270,6 +338,13 @@ impl<B: DisplayBackend> App<B> {
     }
 
     fn update_global_variable(&mut self, name: VarName, value: DynVal) {
+        if crate::tracer::is_active() {
+            crate::tracer::emit(format!("applying update of variable `{}` to `{}`", name, value));
+        }
+        // This is deliberately not wrapped in a `profiler::measure` call: updating a variable
+        // synchronously runs the widget-update listeners, which are measured individually
+        // (see [`crate::state::scope_graph::ScopeGraph::call_listeners_in_scope`]), so an
+        // entry for the whole update would count all of that time a second time.
         let result = self.scope_graph.borrow_mut().update_global_value(&name, value);
         if let Err(err) = result {
             error_handling_ctx::print_error(err);
@@ -278,6 +353,20 @@ impl<B: DisplayBackend> App<B> {
         self.apply_run_while_expressions_mentioning(&name);
     }
 
+    /// Write the content of the key-value store into the `EWW_STORE` variable,
+    /// so that expressions referencing it react to changes made via `eww store`.
+    fn update_store_variable(&mut self) {
+        let store_json: serde_json::Value = self
+            .store
+            .iter()
+            .map(|(key, value)| {
+                (key.clone(), value.as_json_value().unwrap_or_else(|_| serde_json::Value::String(value.to_string())))
+            })
+            .collect::<serde_json::Map<String, serde_json::Value>>()
+            .into();
+        self.update_global_variable(VarName::from("EWW_STORE"), DynVal::from_string(store_json.to_string()));
+    }
+
     /// Variables may be referenced in defpoll :run-while expressions.
     /// Thus, when a variable changes, the run-while conditions of all variables
     /// that mention the changed variable need to be reevaluated and reapplied.
@@ -313,6 +402,10 @@ impl<B: DisplayBackend> App<B> {
             .remove(window_name)
             .with_context(|| format!("Tried to close window named '{}', but no such window was open", window_name))?;
 
+        if crate::tracer::is_active() {
+            crate::tracer::emit(format!("closing window `{}`", window_name));
+        }
+
         let scope_index = eww_window.scope_index;
         eww_window.close();
 
@@ -338,6 +431,9 @@ impl<B: DisplayBackend> App<B> {
     ) -> Result<()> {
         self.failed_windows.remove(window_name);
         log::info!("Opening window {}", window_name);
+        if crate::tracer::is_active() {
+            crate::tracer::emit(format!("opening window `{}`", window_name));
+        }
 
         // if an instance of this is already running, close it
         // TODO make reopening optional via a --no-reopen flag?
@@ -348,6 +444,11 @@ impl<B: DisplayBackend> App<B> {
         let open_result: Result<_> = try {
             let mut window_def = self.eww_config.get_window(window_name)?.clone();
             assert_eq!(window_def.name, window_name, "window definition name did not equal the called window");
+            // Windows without a geometry block can still be given an explicit position and size,
+            // e.g. from the CLI or when restoring a :preserve-geometry window.
+            if window_def.geometry.is_none() && pos.is_some() && size.is_some() {
+                window_def.geometry = Some(WindowGeometry::default());
+            }
             window_def.geometry = window_def.geometry.map(|x| x.override_if_given(anchor, pos, size));
 
             let root_index = self.scope_graph.borrow().root_index;
@@ -369,7 +470,39 @@ impl<B: DisplayBackend> App<B> {
 
             let monitor_geometry = get_monitor_geometry(monitor.or_else(|| window_def.monitor.clone()))?;
 
-            let mut eww_window = initialize_window::<B>(monitor_geometry, root_widget, window_def, window_scope)?;
+            // Clip the monitor area to the EWMH workarea, so that anchored windows don't render
+            // underneath docks and panels of other applications. On wayland, the layer-shell
+            // protocol already keeps surfaces out of other surfaces' exclusive zones.
+            #[cfg(feature = "x11")]
+            let monitor_geometry = if B::IS_X11 && window_def.respect_workarea {
+                match crate::display_backend::get_current_workarea()? {
+                    Some(workarea) => workarea.intersect(&monitor_geometry).unwrap_or(monitor_geometry),
+                    None => monitor_geometry,
+                }
+            } else {
+                monitor_geometry
+            };
+
+            // If the window is positioned relative to another window, its anchoring and geometry are
+            // computed against that window's current rectangle instead of the monitor.
+            let anchor_rect = match &window_def.relative_to {
+                #[cfg(feature = "x11")]
+                Some(target_name) if B::IS_X11 => {
+                    let target = self.open_windows.get(target_name).with_context(|| {
+                        format!("Window '{}' is positioned relative to window '{}', which is not open", window_name, target_name)
+                    })?;
+                    get_gtk_window_rect(&target.gtk_window)?
+                }
+                Some(_) => {
+                    Err(anyhow!("Positioning windows relative to other windows (:relative-to) is only supported on x11"))?
+                }
+                None => monitor_geometry,
+            };
+
+            #[cfg(feature = "x11")]
+            let (relative_to, window_geometry) = (window_def.relative_to.clone(), window_def.geometry);
+
+            let mut eww_window = initialize_window::<B>(anchor_rect, root_widget, window_def, window_scope)?;
             eww_window.gtk_window.style_context().add_class(window_name);
 
             // initialize script var handlers for variables. As starting a scriptvar with the script_var_handler is idempodent,
@@ -425,6 +558,16 @@ impl<B: DisplayBackend> App<B> {
             }
 
             self.open_windows.insert(window_name.to_string(), eww_window);
+
+            #[cfg(feature = "x11")]
+            {
+                if let (Some(target_name), Some(geometry)) = (relative_to, window_geometry) {
+                    self.attach_window_to_target(window_name, &target_name, geometry);
+                }
+                // The previous instance of this window took the configure-event handlers of all
+                // windows attached to it down with it, so re-establish those attachments.
+                self.reattach_windows_to_target(window_name);
+            }
         };
 
         if let Err(err) = open_result {
@@ -435,8 +578,75 @@ impl<B: DisplayBackend> App<B> {
         }
     }
 
+    /// Keep the given window attached to its `:relative-to` target window by re-applying its
+    /// position whenever the target's geometry changes. The connected handler is remembered on the
+    /// attached window, so that it can be disconnected again when the attached window closes.
+    #[cfg(feature = "x11")]
+    fn attach_window_to_target(&mut self, window_name: &str, target_name: &str, geometry: WindowGeometry) {
+        let target_window = match self.open_windows.get(target_name) {
+            Some(target) => target.gtk_window.clone(),
+            None => return,
+        };
+        let attached = match self.open_windows.get_mut(window_name) {
+            Some(attached) => attached,
+            None => return,
+        };
+        // disconnect a handler that may still be connected to a previous instance of the target
+        if let Some((old_target, handler_id)) = attached.attach_event_handler.take() {
+            if let Some(old_target) = old_target.upgrade() {
+                old_target.disconnect(handler_id);
+            }
+        }
+        let attached_window = attached.gtk_window.downgrade();
+        let handler_id = target_window.connect_configure_event(move |target_window, _| {
+            let result: Result<_> = try {
+                if let Some(attached_window) = attached_window.upgrade() {
+                    apply_window_position(geometry, get_gtk_window_rect(target_window)?, &attached_window)?;
+                }
+            };
+            if let Err(err) = result {
+                error_handling_ctx::print_error(err);
+            }
+            false
+        });
+        attached.attach_event_handler = Some((target_window.downgrade(), handler_id));
+    }
+
+    /// Re-establish the attachment of all open windows that are positioned relative to the given
+    /// window, e.g. after that window has been re-instantiated by a reload.
+    #[cfg(feature = "x11")]
+    fn reattach_windows_to_target(&mut self, target_name: &str) {
+        let attached_names: Vec<String> = self
+            .open_windows
+            .keys()
+            .filter(|name| {
+                self.eww_config.get_window(name).map_or(false, |def| def.relative_to.as_deref() == Some(target_name))
+            })
+            .cloned()
+            .collect();
+        for name in attached_names {
+            if let Some(geometry) = self.eww_config.get_window(&name).ok().and_then(|def| def.geometry) {
+                self.attach_window_to_target(&name, target_name, geometry);
+            }
+        }
+    }
+
     /// Load the given configuration, reloading all script-vars and attempting to reopen all windows that where opened.
     pub fn load_config(&mut self, config: config::EwwConfig) -> Result<()> {
+        // When nothing but window definitions changed, only the windows whose definition actually
+        // differs need to be re-instantiated. This leaves the variable state and the GTK state of
+        // all other windows (scroll positions, input contents, ...) intact.
+        // If no tracked window changed either, still take the full-reload path below:
+        // reloading an unchanged configuration is an established way to restart dead
+        // script-var processes and to re-instantiate windows from scratch.
+        if !self.eww_config.is_safe_mode() && self.eww_config.only_windows_changed(&config) {
+            let changed_windows = self.changed_tracked_windows(&config);
+            if !changed_windows.is_empty() {
+                log::info!("Reloading changed windows");
+                return self.swap_changed_windows(config, changed_windows);
+            }
+        }
+
         log::info!("Reloading windows");
 
         self.script_var_handler.stop_all();
@@ -445,13 +655,112 @@ impl<B: DisplayBackend> App<B> {
 
         log::trace!("loading config: {:#?}", config);
 
+        // Capture the actual geometry of all open windows that have :preserve-geometry set,
+        // so that runtime position and size adjustments survive the reload.
+        // This requires asking the window for its position, which only works on x11.
+        let mut preserved_geometry = HashMap::new();
+        for (name, window) in &self.open_windows {
+            if config.get_window(name).map_or(false, |def| def.preserve_geometry) {
+                if B::IS_X11 {
+                    preserved_geometry.insert(name.clone(), capture_window_geometry(&window.gtk_window));
+                } else {
+                    log::warn!("Ignoring :preserve-geometry of window {}, as it is only supported on x11", name);
+                }
+            }
+        }
+
+        let was_safe_mode = self.eww_config.is_safe_mode();
         self.eww_config = config;
         self.scope_graph.borrow_mut().clear(self.eww_config.generate_initial_state()?);
+        // clearing the scope graph reset `EWW_STORE` to its initial value, so write the actual store content back
+        self.update_store_variable();
 
-        let window_names: Vec<String> =
+        let mut window_names: Vec<String> =
             self.open_windows.keys().cloned().chain(self.failed_windows.iter().cloned()).dedup().collect();
+        // When leaving safe mode, the safe-mode window gets closed because it no longer exists in the
+        // new configuration, which would leave the daemon without any windows. Instead, open the windows
+        // the fixed configuration marks as open-by-default, like on daemon startup.
+        if was_safe_mode {
+            for def in self.eww_config.get_windows().values() {
+                if def.open_by_default && !window_names.contains(&def.name) {
+                    window_names.push(def.name.clone());
+                }
+            }
+        }
+        // windows that are positioned relative to another window must be opened after their target window
+        window_names.sort_by_key(|name| self.eww_config.get_window(name).map_or(false, |def| def.relative_to.is_some()));
         for window_name in &window_names {
-            self.open_window(window_name, None, None, None, None, None)?;
+            // Windows that no longer exist in the new configuration (such as the safe-mode window
+            // after the configuration has been fixed) are closed instead of reopened.
+            if !self.eww_config.get_windows().contains_key(window_name) {
+                log::info!("Closing window {} as it no longer exists in the configuration", window_name);
+                if self.open_windows.contains_key(window_name) {
+                    self.close_window(window_name)?;
+                } else {
+                    self.failed_windows.remove(window_name);
+                }
+                continue;
+            }
+            match preserved_geometry.remove(window_name) {
+                // The captured position is relative to the top left of the captured monitor,
+                // so the configured anchor needs to be overridden accordingly.
+                Some((monitor, pos, size)) => {
+                    self.open_window(window_name, Some(pos), Some(size), monitor, Some(AnchorPoint::default()), None)?
+                }
+                None => self.open_window(window_name, None, None, None, None, None)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the names of all open or failed windows whose definition differs between the current
+    /// and the given configuration, based on the span-insensitive definition hashes.
+    fn changed_tracked_windows(&self, config: &config::EwwConfig) -> Vec<String> {
+        self.open_windows
+            .keys()
+            .cloned()
+            .chain(self.failed_windows.iter().cloned())
+            .dedup()
+            .filter(|name| self.eww_config.get_window_content_hash(name) != config.get_window_content_hash(name))
+            .collect()
+    }
+
+    /// Re-instantiate only the given windows with their definition from the given configuration.
+    /// Must only be called when nothing but window definitions changed
+    /// (see [`config::EwwConfig::only_windows_changed`]).
+    fn swap_changed_windows(&mut self, config: config::EwwConfig, mut changed_windows: Vec<String>) -> Result<()> {
+        let mut preserved_geometry = HashMap::new();
+        for name in &changed_windows {
+            if let Some(window) = self.open_windows.get(name) {
+                if B::IS_X11 && config.get_window(name).map_or(false, |def| def.preserve_geometry) {
+                    preserved_geometry.insert(name.clone(), capture_window_geometry(&window.gtk_window));
+                }
+            }
+        }
+
+        self.eww_config = config;
+
+        // windows that are positioned relative to another window must be opened after their target window
+        changed_windows.sort_by_key(|name| self.eww_config.get_window(name).map_or(false, |def| def.relative_to.is_some()));
+        for window_name in &changed_windows {
+            if !self.eww_config.get_windows().contains_key(window_name) {
+                log::info!("Closing window {} as it no longer exists in the configuration", window_name);
+                if self.open_windows.contains_key(window_name) {
+                    self.close_window(window_name)?;
+                } else {
+                    self.failed_windows.remove(window_name);
+                }
+                continue;
+            }
+            log::info!("Reloading window {} as its definition changed", window_name);
+            match preserved_geometry.remove(window_name) {
+                // The captured position is relative to the top left of the captured monitor,
+                // so the configured anchor needs to be overridden accordingly.
+                Some((monitor, pos, size)) => {
+                    self.open_window(window_name, Some(pos), Some(size), monitor, Some(AnchorPoint::default()), None)?
+                }
+                None => self.open_window(window_name, None, None, None, None, None)?,
+            }
         }
         Ok(())
     }
@@ -497,6 +806,12 @@ fn initialize_window<B: DisplayBackend>(
         window.set_size_request(actual_window_rect.width(), actual_window_rect.height());
         window.set_default_size(actual_window_rect.width(), actual_window_rect.height());
     }
+
+    // Wallpaper-mode windows size to the full monitor and pass all input through to whatever is below.
+    if window_def.wallpaper {
+        window.set_default_size(monitor_geometry.width(), monitor_geometry.height());
+        window.input_shape_combine_region(Some(&gtk::cairo::Region::create()));
+    }
     window.set_decorated(false);
     window.set_skip_taskbar_hint(true);
     window.set_skip_pager_hint(true);
@@ -509,6 +824,9 @@ fn initialize_window<B: DisplayBackend>(
 
     window.realize();
 
+    #[allow(unused_mut)]
+    let mut screen_size_handler: Option<(gdk::Screen, glib::SignalHandlerId)> = None;
+
     #[cfg(feature = "x11")]
     if B::IS_X11 {
         if let Some(geometry) = window_def.geometry {
@@ -520,12 +838,72 @@ fn initialize_window<B: DisplayBackend>(
                 });
             }
         }
+        if window_def.wallpaper {
+            if let Some(gdk_window) = window.window() {
+                gdk_window.move_resize(
+                    monitor_geometry.x(),
+                    monitor_geometry.y(),
+                    monitor_geometry.width(),
+                    monitor_geometry.height(),
+                );
+            }
+            // Re-apply the full-monitor geometry whenever the resolution changes,
+            // so the wallpaper keeps covering the whole monitor.
+            if let Some(screen) = gtk::prelude::GtkWindowExt::screen(&window) {
+                let monitor = window_def.monitor.clone();
+                let window_ref = window.downgrade();
+                let handler_id = screen.connect_size_changed(move |_| {
+                    let result: Result<_> = try {
+                        if let (Some(window), Ok(monitor_geometry)) =
+                            (window_ref.upgrade(), get_monitor_geometry(monitor.clone()))
+                        {
+                            let gdk_window = window.window().context("Failed to get gdk window from gtk window")?;
+                            gdk_window.move_resize(
+                                monitor_geometry.x(),
+                                monitor_geometry.y(),
+                                monitor_geometry.width(),
+                                monitor_geometry.height(),
+                            );
+                        }
+                    };
+                    if let Err(err) = result {
+                        error_handling_ctx::print_error(err);
+                    }
+                });
+                screen_size_handler = Some((screen, handler_id));
+            }
+        }
+
         display_backend::set_xprops(&window, monitor_geometry, &window_def)?;
     }
 
     window.show_all();
 
-    Ok(EwwWindow { name: window_def.name, gtk_window: window, scope_index: window_scope, destroy_event_handler_id: None })
+    // On x11, the wayland layer-shell keyboard-mode is not available,
+    // so the keyboard of grab-windows needs to be grabbed through the gdk seat instead.
+    // This can only happen after the window has been mapped.
+    #[cfg(feature = "x11")]
+    if B::IS_X11 && window_def.grab {
+        use gtk::prelude::SeatExt;
+        let window_name = window_def.name.clone();
+        window.connect_map(move |window| {
+            if let (Some(gdk_window), Some(seat)) = (window.window(), window.display().default_seat()) {
+                let status = seat.grab(&gdk_window, gdk::SeatCapabilities::KEYBOARD, false, None, None, None);
+                if status != gdk::GrabStatus::Success {
+                    log::warn!("Failed to grab keyboard for window {}: {:?}", window_name, status);
+                }
+            }
+        });
+    }
+
+    Ok(EwwWindow {
+        name: window_def.name,
+        gtk_window: window,
+        scope_index: window_scope,
+        destroy_event_handler_id: None,
+        attach_event_handler: None,
+        screen_size_handler,
+    })
 }
 
 /// Apply the provided window-positioning rules to the window.
@@ -548,6 +926,32 @@ fn apply_window_position(
     Ok(())
 }
 
+/// Capture the monitor, position and size of an open window, so that it can be reopened with the same geometry.
+/// The position is returned relative to the top left corner of the monitor the window is on.
+fn capture_window_geometry(window: &gtk::Window) -> (Option<MonitorIdentifier>, Coords, Coords) {
+    let size = Coords::from_pixels(window.size());
+    let (mut x, mut y) = window.position();
+    let display = window.display();
+    let monitor = window
+        .window()
+        .and_then(|gdk_window| display.monitor_at_window(&gdk_window))
+        .and_then(|monitor| (0..display.n_monitors()).find(|&i| display.monitor(i).as_ref() == Some(&monitor)));
+    if let Some(monitor_geometry) = monitor.and_then(|i| display.monitor(i)).map(|m| m.geometry()) {
+        x -= monitor_geometry.x();
+        y -= monitor_geometry.y();
+    }
+    (monitor.map(MonitorIdentifier::Numeric), Coords::from_pixels((x, y)), size)
+}
+
+/// Get the current position and size of a window, as a rectangle that other windows can be anchored against.
+#[cfg(feature = "x11")]
+fn get_gtk_window_rect(window: &gtk::Window) -> Result<gdk::Rectangle> {
+    let gdk_window = window.window().context("Failed to get gdk window from gtk window")?;
+    let origin = gdk_window.origin();
+    let (width, height) = window.size();
+    Ok(gdk::Rectangle::new(origin.1, origin.2, width, height))
+}
+
 fn on_screen_changed(window: &gtk::Window, _old_screen: Option<&gdk::Screen>) {
     let visual = gtk::prelude::GtkWindowExt::screen(window)
         .and_then(|screen| screen.rgba_visual().filter(|_| screen.is_composited()).or_else(|| screen.system_visual()));
@@ -7,7 +7,7 @@ use crate::{
 };
 use anyhow::{Context, Result};
 use std::{
-    io::{Read, Write},
+    io::{BufRead, Read, Write},
     os::unix::net::UnixStream,
 };
 
@@ -47,3 +47,16 @@ pub fn do_server_call(stream: &mut UnixStream, action: &opts::ActionWithServer)
         Some(buf)
     })
 }
+
+/// Connect to the daemon's event trace (see [`opts::ActionWithServer::Trace`]),
+/// printing the received events to stdout until the connection is terminated.
+pub fn handle_trace(stream: &mut UnixStream) -> Result<()> {
+    let message_bytes = bincode::serialize(&opts::ActionWithServer::Trace)?;
+    stream.write(&(message_bytes.len() as u32).to_be_bytes()).context("Failed to send command size header to IPC stream")?;
+    stream.write_all(&message_bytes).context("Failed to write command to IPC stream")?;
+
+    for line in std::io::BufReader::new(stream).lines() {
+        println!("{}", line.context("Error reading trace event from server")?);
+    }
+    Ok(())
+}
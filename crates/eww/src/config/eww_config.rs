@@ -3,11 +3,12 @@ use eww_shared_util::VarName;
 use std::collections::HashMap;
 use yuck::{
     config::{
-        script_var_definition::ScriptVarDefinition, validate::ValidationError, widget_definition::WidgetDefinition,
-        window_definition::WindowDefinition, Config,
+        file_provider::YuckFileProvider, script_var_definition::ScriptVarDefinition, validate::ValidationError,
+        widget_definition::WidgetDefinition, window_definition::WindowDefinition, Config,
     },
     error::DiagError,
     format_diagnostic::ToDiagnostic,
+    parser::from_ast::FromAstElementContent,
 };
 
 use simplexpr::dynval::DynVal;
@@ -23,6 +24,17 @@ pub fn read_from_eww_paths(eww_paths: &EwwPaths) -> Result<EwwConfig> {
     EwwConfig::read_from_dir(&mut error_handling_ctx::FILE_DATABASE.write().unwrap(), eww_paths)
 }
 
+/// Configuration used by [`EwwConfig::generate_safe_mode`] when the user configuration fails to load.
+const SAFE_MODE_YUCK: &str = r#"
+(defvar safe_mode_error "")
+(defwindow safe-mode
+  :open-by-default true
+  (box :orientation "v" :space-evenly false :spacing 10
+    (label :text "Eww failed to load your configuration")
+    (label :text safe_mode_error :xalign 0)
+    (label :text "Fix the error and run `eww reload` to exit safe mode.")))
+"#;
+
 /// Eww configuration structure.
 #[derive(Debug, Clone, Default)]
 pub struct EwwConfig {
@@ -33,6 +45,13 @@ pub struct EwwConfig {
 
     // map of variables to all pollvars which refer to them in their run-while-expression
     run_while_mentions: HashMap<VarName, Vec<VarName>>,
+
+    // span-insensitive hashes of all toplevel definitions, used to detect which definitions
+    // actually changed across a config reload (see [`yuck::config::Config::content_hashes`])
+    content_hashes: HashMap<(&'static str, String), u64>,
+
+    // whether this is the fallback configuration generated by [`EwwConfig::generate_safe_mode`]
+    safe_mode: bool,
 }
 
 impl EwwConfig {
@@ -61,7 +80,7 @@ impl EwwConfig {
             }
         }
 
-        let Config { widget_definitions, window_definitions, mut var_definitions, mut script_vars } = config;
+        let Config { widget_definitions, window_definitions, mut var_definitions, mut script_vars, content_hashes } = config;
         script_vars.extend(inbuilt::get_inbuilt_vars());
         var_definitions.extend(inbuilt::get_magic_constants(eww_paths));
 
@@ -80,9 +99,60 @@ impl EwwConfig {
             initial_variables: var_definitions.into_iter().map(|(k, v)| (k, v.initial_value)).collect(),
             script_vars,
             run_while_mentions,
+            content_hashes,
+            safe_mode: false,
+        })
+    }
+
+    /// Generate a minimal fallback configuration that only opens a window displaying the given
+    /// error message. Used to keep the daemon alive when the user configuration fails to load,
+    /// so that `eww reload` still works once the configuration is fixed.
+    pub fn generate_safe_mode(files: &mut FileDatabase, error_message: String) -> Result<Self> {
+        let (_, asts) = files.load_yuck_str("<eww safe mode>".to_string(), SAFE_MODE_YUCK.to_string())?;
+        let Config { widget_definitions, window_definitions, var_definitions, script_vars, content_hashes } =
+            Config::generate(files, asts)?;
+
+        let mut initial_variables: HashMap<VarName, DynVal> =
+            var_definitions.into_iter().map(|(k, v)| (k, v.initial_value)).collect();
+        initial_variables.insert(VarName::from("safe_mode_error"), DynVal::from_string(error_message));
+
+        Ok(EwwConfig {
+            windows: window_definitions,
+            widgets: widget_definitions,
+            initial_variables,
+            script_vars,
+            run_while_mentions: HashMap::new(),
+            content_hashes,
+            safe_mode: true,
         })
     }
 
+    /// Whether this is the fallback configuration generated by [`EwwConfig::generate_safe_mode`].
+    pub fn is_safe_mode(&self) -> bool {
+        self.safe_mode
+    }
+
+    /// Check whether this configuration differs from `other` at most in its window definitions.
+    /// If so, a reload only needs to re-instantiate the windows whose definition actually changed
+    /// (see [`crate::app::App::load_config`]). The comparison is based on the span-insensitive
+    /// definition hashes, so edits that merely shift source positions around don't count as changes.
+    pub fn only_windows_changed(&self, other: &Self) -> bool {
+        let non_window_hashes = |config: &Self| -> HashMap<(&'static str, String), u64> {
+            config
+                .content_hashes
+                .iter()
+                .filter(|((element_name, _), _)| *element_name != WindowDefinition::ELEMENT_NAME)
+                .map(|(key, hash)| (key.clone(), *hash))
+                .collect()
+        };
+        non_window_hashes(self) == non_window_hashes(other)
+    }
+
+    /// Get the span-insensitive hash of the window definition with the given name, if it exists.
+    pub fn get_window_content_hash(&self, name: &str) -> Option<u64> {
+        self.content_hashes.get(&(WindowDefinition::ELEMENT_NAME, name.to_string())).copied()
+    }
+
     // TODO this is kinda ugly
     pub fn generate_initial_state(&self) -> Result<HashMap<VarName, DynVal>> {
         let mut vars = self
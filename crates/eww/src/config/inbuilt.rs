@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use simplexpr::{dynval::DynVal, SimplExpr};
 use yuck::config::{
-    script_var_definition::{PollScriptVar, ScriptVarDefinition, VarSource},
+    script_var_definition::{PollScriptVar, ScriptVarDefinition, ScriptVarFormat, VarSource},
     var_definition::VarDefinition,
 };
 
@@ -21,6 +21,7 @@ macro_rules! define_builtin_vars {
                     command: VarSource::Function($fun),
                     initial_value: None,
                     interval: std::time::Duration::from_secs($interval),
+                    format: ScriptVarFormat::Auto,
                     name_span: eww_shared_util::span::Span::DUMMY,
                 })
                 ),*
@@ -95,4 +96,6 @@ define_magic_constants! { eww_paths,
     "EWW_EXECUTABLE" => DynVal::from_string(
         std::env::current_exe().map(|x| x.to_string_lossy().into_owned()).unwrap_or_else(|_| "eww".to_string()),
     ),
+    // @desc EWW_STORE - Json object containing the key-value store managed via `eww store`. I.e.: `${EWW_STORE["my-key"]}`
+    "EWW_STORE" => DynVal::from_string("{}".to_string()),
 }
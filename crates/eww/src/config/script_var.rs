@@ -5,7 +5,7 @@ use codespan_reporting::diagnostic::Severity;
 use eww_shared_util::{Span, VarName};
 use simplexpr::dynval::DynVal;
 use yuck::{
-    config::script_var_definition::{ScriptVarDefinition, VarSource},
+    config::script_var_definition::{ScriptVarDefinition, ScriptVarFormat, VarSource},
     error::DiagError,
     gen_diagnostic,
 };
@@ -19,6 +19,23 @@ pub fn create_script_var_failed_warn(span: Span, var_name: &VarName, error_outpu
     })
 }
 
+/// Validate the output of a script variable against the format declared via `:as`.
+pub fn validate_output_format(span: Span, var_name: &VarName, format: ScriptVarFormat, value: &DynVal) -> Result<()> {
+    let result = match format {
+        ScriptVarFormat::Auto => return Ok(()),
+        ScriptVarFormat::Json => value.as_json_value().map(|_| ()),
+        ScriptVarFormat::Number => value.as_f64().map(|_| ()),
+    };
+    result.map_err(|e| {
+        anyhow!(DiagError(gen_diagnostic! {
+            kind = Severity::Warning,
+            msg = format!("The script for the `{}`-variable produced output that is not valid {}", var_name, format),
+            label = span => "Defined here",
+            note = e.to_string(),
+        }))
+    })
+}
+
 pub fn initial_value(var: &ScriptVarDefinition) -> Result<DynVal> {
     match var {
         ScriptVarDefinition::Poll(x) => match &x.initial_value {
@@ -28,7 +45,10 @@ pub fn initial_value(var: &ScriptVarDefinition) -> Result<DynVal> {
                     .map_err(|err| anyhow!(err))
                     .with_context(|| format!("Failed to compute initial value for {}", &var.name())),
                 VarSource::Shell(span, command) => {
-                    run_command(command).map_err(|e| anyhow!(create_script_var_failed_warn(*span, var.name(), &e.to_string())))
+                    let value = run_command(command)
+                        .map_err(|e| anyhow!(create_script_var_failed_warn(*span, var.name(), &e.to_string())))?;
+                    validate_output_format(*span, var.name(), x.format, &value)?;
+                    Ok(value)
                 }
             },
         },
@@ -48,3 +68,21 @@ pub fn run_command(cmd: &str) -> Result<DynVal> {
     let output = output.trim_matches('\n');
     Ok(DynVal::from(output))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn validate(format: ScriptVarFormat, value: &str) -> Result<()> {
+        validate_output_format(Span::DUMMY, &VarName::from("foo"), format, &DynVal::from(value))
+    }
+
+    #[test]
+    fn test_validate_output_format() {
+        assert!(validate(ScriptVarFormat::Auto, "anything goes {").is_ok());
+        assert!(validate(ScriptVarFormat::Json, r#"{"a": [1, 2]}"#).is_ok());
+        assert!(validate(ScriptVarFormat::Json, r#"{"a": [1, 2]"#).is_err());
+        assert!(validate(ScriptVarFormat::Number, "12.5").is_ok());
+        assert!(validate(ScriptVarFormat::Number, "twelve").is_err());
+    }
+}
@@ -28,7 +28,45 @@ pub fn parse_scss_from_config(path: &Path) -> anyhow::Result<(usize, String)> {
         (scss_file, css)
     };
 
+    let mut css = css;
+    css.push_str(&parse_window_scss(path)?);
+
     let mut file_db = error_handling_ctx::FILE_DATABASE.write().unwrap();
     let file_id = file_db.insert_string(s_css_path.display().to_string(), css.clone())?;
     Ok((file_id, css))
 }
+
+/// Read all (s)css files in the `windows` subdirectory of the config dir.
+/// The styles of each file are wrapped in a class selector named after the file,
+/// scoping them to the window of the same name so they cannot leak into other windows.
+fn parse_window_scss(config_dir: &Path) -> anyhow::Result<String> {
+    let windows_dir = config_dir.join("windows");
+    if !windows_dir.exists() {
+        return Ok(String::new());
+    }
+
+    let mut entries = windows_dir
+        .read_dir()
+        .with_context(|| format!("Failed to read directory {}", windows_dir.display()))?
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.path());
+
+    let mut css = String::new();
+    for entry in entries {
+        let file_path = entry.path();
+        let extension = file_path.extension().unwrap_or_default();
+        if extension != "scss" && extension != "css" {
+            continue;
+        }
+        let window_name = file_path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let file_content = std::fs::read_to_string(&file_path)
+            .with_context(|| format!("Failed to read stylesheet {}", file_path.display()))?;
+        let file_content = replace_env_var_references(file_content);
+        let grass_config = grass::Options::default().load_path(config_dir);
+        let compiled = grass::from_string(format!(".{} {{ {} }}", window_name, file_content), &grass_config)
+            .map_err(|err| anyhow!("SCSS parsing error in {}: {}", file_path.display(), err))?;
+        css.push_str(&compiled);
+        css.push('\n');
+    }
+    Ok(css)
+}
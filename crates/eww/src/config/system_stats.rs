@@ -52,6 +52,16 @@ pub fn get_ram() -> String {
     )
 }
 
+/// Resident and virtual memory usage of the eww daemon process itself, in bytes.
+pub fn get_daemon_memory_usage() -> Result<(u64, u64)> {
+    use sysinfo::ProcessExt;
+    let mut c = SYSTEM.lock().unwrap();
+    let pid = sysinfo::get_current_pid().map_err(|e| anyhow::anyhow!("Failed to get pid of the daemon process: {}", e))?;
+    c.refresh_process(pid);
+    let process = c.process(pid).context("Failed to look up the daemon process")?;
+    Ok((process.memory(), process.virtual_memory()))
+}
+
 pub fn get_temperatures() -> String {
     let mut c = SYSTEM.lock().unwrap();
     c.refresh_components_list();
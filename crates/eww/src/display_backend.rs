@@ -4,7 +4,7 @@ use yuck::config::window_definition::WindowDefinition;
 pub use platform_wayland::WaylandBackend;
 
 #[cfg(feature = "x11")]
-pub use platform_x11::{set_xprops, X11Backend};
+pub use platform_x11::{get_current_workarea, set_xprops, X11Backend};
 
 pub trait DisplayBackend: Send + Sync + 'static {
     const IS_X11: bool;
@@ -106,6 +106,39 @@ mod platform_wayland {
             if window_def.backend_options.wayland.exclusive {
                 gtk_layer_shell::auto_exclusive_zone_enable(&window);
             }
+
+            // Grab-mode windows cover the whole output on the overlay layer and take exclusive
+            // keyboard focus, which is what lock-screens and confirmation dialogs want.
+            if window_def.grab {
+                gtk_layer_shell::set_layer(&window, gtk_layer_shell::Layer::Overlay);
+                gtk_layer_shell::set_keyboard_mode(&window, gtk_layer_shell::KeyboardMode::Exclusive);
+                gtk_layer_shell::set_exclusive_zone(&window, -1);
+                for edge in [
+                    gtk_layer_shell::Edge::Left,
+                    gtk_layer_shell::Edge::Right,
+                    gtk_layer_shell::Edge::Top,
+                    gtk_layer_shell::Edge::Bottom,
+                ] {
+                    gtk_layer_shell::set_anchor(&window, edge, true);
+                }
+            }
+
+            // Wallpaper-mode windows cover the whole output on the background layer and ignore
+            // all input. Anchoring to every edge makes the compositor resize the surface whenever
+            // the output's resolution changes.
+            if window_def.wallpaper {
+                gtk_layer_shell::set_layer(&window, gtk_layer_shell::Layer::Background);
+                gtk_layer_shell::set_keyboard_interactivity(&window, false);
+                gtk_layer_shell::set_exclusive_zone(&window, -1);
+                for edge in [
+                    gtk_layer_shell::Edge::Left,
+                    gtk_layer_shell::Edge::Right,
+                    gtk_layer_shell::Edge::Top,
+                    gtk_layer_shell::Edge::Bottom,
+                ] {
+                    gtk_layer_shell::set_anchor(&window, edge, true);
+                }
+            }
             Some(window)
         }
     }
@@ -146,6 +179,16 @@ mod platform_x11 {
             } else {
                 window.unstick();
             }
+            if window_def.grab {
+                window.fullscreen();
+                window.set_keep_above(true);
+            }
+            if window_def.wallpaper {
+                window.set_type_hint(gdk::WindowTypeHint::Desktop);
+                window.set_keep_below(true);
+                window.stick();
+                window.set_accept_focus(false);
+            }
             Some(window)
         }
     }
@@ -156,6 +199,14 @@ mod platform_x11 {
         Ok(())
     }
 
+    /// Query the EWMH workarea (`_NET_WORKAREA`) of the current desktop,
+    /// i.e. the part of the root window that is not reserved by panels via struts.
+    /// Returns `None` if the window manager does not provide a workarea.
+    pub fn get_current_workarea() -> Result<Option<gdk::Rectangle>> {
+        let backend = X11BackendConnection::new()?;
+        backend.get_current_workarea()
+    }
+
     struct X11BackendConnection {
         conn: RustConnection<DefaultStream>,
         root_window: u32,
@@ -170,6 +221,31 @@ mod platform_x11 {
             Ok(X11BackendConnection { conn, root_window: screen.root, atoms })
         }
 
+        fn get_current_workarea(&self) -> Result<Option<gdk::Rectangle>> {
+            let current_desktop = self
+                .conn
+                .get_property(false, self.root_window, self.atoms._NET_CURRENT_DESKTOP, self.atoms.CARDINAL, 0, 1)?
+                .reply()?
+                .value32()
+                .and_then(|mut values| values.next())
+                .unwrap_or(0);
+
+            let workarea_reply = self
+                .conn
+                .get_property(false, self.root_window, self.atoms._NET_WORKAREA, self.atoms.CARDINAL, 0, u32::MAX)?
+                .reply()?;
+            let workareas: Vec<u32> = match workarea_reply.value32() {
+                Some(values) => values.collect(),
+                None => return Ok(None),
+            };
+
+            // _NET_WORKAREA contains one x, y, width, height quadruple per desktop
+            let offset = current_desktop as usize * 4;
+            Ok(workareas.get(offset..offset + 4).map(|workarea| {
+                gdk::Rectangle::new(workarea[0] as i32, workarea[1] as i32, workarea[2] as i32, workarea[3] as i32)
+            }))
+        }
+
         fn set_xprops_for(
             &self,
             window: &gtk::Window,
@@ -266,6 +342,8 @@ mod platform_x11 {
             _NET_WM_NAME,
             _NET_WM_STRUT,
             _NET_WM_STRUT_PARTIAL,
+            _NET_WORKAREA,
+            _NET_CURRENT_DESKTOP,
             WM_NAME,
             UTF8_STRING,
             COMPOUND_TEXT,
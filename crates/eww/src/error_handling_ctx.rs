@@ -1,7 +1,11 @@
 //! Disgusting global state.
 //! I hate this, but [buffet](https://github.com/buffet) told me that this is what I should do for peak maintainability!
 
-use std::sync::{Arc, RwLock};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant},
+};
 
 use codespan_reporting::{
     diagnostic::Diagnostic,
@@ -20,13 +24,63 @@ pub fn clear_files() {
     *FILE_DATABASE.write().unwrap() = FileDatabase::new();
 }
 
+/// Minimum time between repeated prints of the same error message.
+const ERROR_RATE_LIMIT: Duration = Duration::from_secs(10);
+
+static RECENT_ERRORS: Lazy<Mutex<HashMap<String, ErrorLogEntry>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Default)]
+struct ErrorLogEntry {
+    last_printed: Option<Instant>,
+    suppressed: u32,
+}
+
+impl ErrorLogEntry {
+    /// Record an occurrence of the message at `now`. Returns the number of occurrences that were
+    /// swallowed since the last print if the message should be printed again, or `None` if the
+    /// message is still within the rate limit and should be suppressed.
+    fn register_occurrence(&mut self, now: Instant) -> Option<u32> {
+        match self.last_printed {
+            Some(last) if now.duration_since(last) < ERROR_RATE_LIMIT => {
+                self.suppressed += 1;
+                None
+            }
+            _ => {
+                let suppressed = self.suppressed;
+                self.last_printed = Some(now);
+                self.suppressed = 0;
+                Some(suppressed)
+            }
+        }
+    }
+}
+
 pub fn print_error(err: anyhow::Error) {
     match anyhow_err_to_diagnostic(&err) {
         Some(diag) => match stringify_diagnostic(diag) {
-            Ok(diag) => eprintln!("{}", diag),
-            Err(_) => log::error!("{:?}", err),
+            Ok(diag) => print_deduplicated(diag),
+            Err(_) => print_deduplicated(format!("{:?}", err)),
         },
-        None => log::error!("{:?}", err),
+        None => print_deduplicated(format!("{:?}", err)),
+    }
+}
+
+/// Print an error message, rate-limiting repetitions of the same message:
+/// a message that was already printed recently is only counted, and a
+/// "repeated N more times" note is printed the next time it surfaces.
+/// This keeps e.g. an expression that fails on every poll tick from drowning out other errors.
+fn print_deduplicated(message: String) {
+    let mut recent = RECENT_ERRORS.lock().unwrap();
+    let now = Instant::now();
+    // forget errors that haven't occurred in a while, so the map doesn't grow indefinitely
+    recent.retain(|_, entry| entry.last_printed.map_or(true, |last| now.duration_since(last) < ERROR_RATE_LIMIT * 2));
+
+    let entry = recent.entry(message.clone()).or_default();
+    if let Some(suppressed) = entry.register_occurrence(now) {
+        if suppressed > 0 {
+            eprintln!("(the following error was repeated {} more times while it was suppressed)", suppressed);
+        }
+        eprintln!("{}", message);
     }
 }
 
@@ -37,6 +91,13 @@ pub fn format_error(err: &anyhow::Error) -> String {
     anyhow_err_to_diagnostic(err).and_then(|diag| stringify_diagnostic(diag).ok()).unwrap_or_else(|| format!("{:?}", err))
 }
 
+/// Like [`format_error`], but without ANSI color codes, for showing the error outside of a terminal.
+pub fn format_error_plain(err: &anyhow::Error) -> String {
+    anyhow_err_to_diagnostic(err)
+        .and_then(|diag| stringify_diagnostic_plain(diag).ok())
+        .unwrap_or_else(|| format!("{:?}", err))
+}
+
 pub fn anyhow_err_to_diagnostic(err: &anyhow::Error) -> Option<Diagnostic<usize>> {
     #[allow(clippy::manual_map)]
     if let Some(err) = err.downcast_ref::<DiagError>() {
@@ -52,7 +113,23 @@ pub fn anyhow_err_to_diagnostic(err: &anyhow::Error) -> Option<Diagnostic<usize>
     }
 }
 
-pub fn stringify_diagnostic(mut diagnostic: codespan_reporting::diagnostic::Diagnostic<usize>) -> anyhow::Result<String> {
+pub fn stringify_diagnostic(diagnostic: codespan_reporting::diagnostic::Diagnostic<usize>) -> anyhow::Result<String> {
+    let mut buf = Vec::new();
+    emit_diagnostic(&mut term::termcolor::Ansi::new(&mut buf), diagnostic)?;
+    Ok(String::from_utf8(buf)?)
+}
+
+/// Like [`stringify_diagnostic`], but without ANSI color codes.
+pub fn stringify_diagnostic_plain(diagnostic: codespan_reporting::diagnostic::Diagnostic<usize>) -> anyhow::Result<String> {
+    let mut buf = Vec::new();
+    emit_diagnostic(&mut term::termcolor::NoColor::new(&mut buf), diagnostic)?;
+    Ok(String::from_utf8(buf)?)
+}
+
+fn emit_diagnostic(
+    writer: &mut dyn term::termcolor::WriteColor,
+    mut diagnostic: codespan_reporting::diagnostic::Diagnostic<usize>,
+) -> anyhow::Result<()> {
     diagnostic.labels.retain(|label| !Span(label.range.start, label.range.end, label.file_id).is_dummy());
 
     let mut config = term::Config::default();
@@ -60,9 +137,28 @@ pub fn stringify_diagnostic(mut diagnostic: codespan_reporting::diagnostic::Diag
     chars.single_primary_caret = '─';
     config.chars = chars;
     config.chars.note_bullet = '→';
-    let mut buf = Vec::new();
-    let mut writer = term::termcolor::Ansi::new(&mut buf);
     let files = FILE_DATABASE.read().unwrap();
-    term::emit(&mut writer, &config, &*files, &diagnostic)?;
-    Ok(String::from_utf8(buf)?)
+    term::emit(writer, &config, &*files, &diagnostic)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_error_rate_limiting() {
+        let mut entry = ErrorLogEntry::default();
+        let start = Instant::now();
+        // the first occurrence is printed immediately, with nothing suppressed before it
+        assert_eq!(entry.register_occurrence(start), Some(0));
+        // occurrences within the rate limit are only counted
+        assert_eq!(entry.register_occurrence(start + Duration::from_secs(1)), None);
+        assert_eq!(entry.register_occurrence(start + Duration::from_secs(2)), None);
+        // once the rate limit has passed, the message is printed again with the suppressed count
+        assert_eq!(entry.register_occurrence(start + ERROR_RATE_LIMIT), Some(2));
+        // which also resets the rate limit window and the count
+        assert_eq!(entry.register_occurrence(start + ERROR_RATE_LIMIT + Duration::from_secs(1)), None);
+        assert_eq!(entry.register_occurrence(start + ERROR_RATE_LIMIT * 2), Some(1));
+    }
 }
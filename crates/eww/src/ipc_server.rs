@@ -1,21 +1,28 @@
 use crate::{app, opts};
-use anyhow::{Context, Result};
-use std::time::Duration;
+use anyhow::{bail, Context, Result};
+use nix::unistd::{Gid, Uid};
+use std::{path::Path, time::Duration};
 use tokio::{
-    io::{AsyncReadExt, AsyncWriteExt},
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
     sync::mpsc::*,
 };
 
-pub async fn run_server<P: AsRef<std::path::Path>>(evt_send: UnboundedSender<app::DaemonCommand>, socket_path: P) -> Result<()> {
+pub async fn run_server<P: AsRef<std::path::Path>>(
+    evt_send: UnboundedSender<app::DaemonCommand>,
+    socket_path: P,
+    allowed_group: Option<String>,
+) -> Result<()> {
     let socket_path = socket_path.as_ref();
+    let allowed_gid = allowed_group.as_deref().map(lookup_group).transpose()?;
     let listener = { tokio::net::UnixListener::bind(socket_path)? };
+    restrict_socket_permissions(socket_path, allowed_gid)?;
     log::info!("IPC server initialized");
     crate::loop_select_exiting! {
         connection = listener.accept() => match connection {
             Ok((stream, _addr)) => {
                 let evt_send = evt_send.clone();
                 tokio::spawn(async move {
-                    let result = handle_connection(stream, evt_send.clone()).await;
+                    let result = handle_connection(stream, evt_send.clone(), allowed_gid).await;
                     crate::print_result_err!("while handling IPC connection with client", result);
                 });
             },
@@ -25,14 +32,76 @@ pub async fn run_server<P: AsRef<std::path::Path>>(evt_send: UnboundedSender<app
     Ok(())
 }
 
+/// Listen for commands on a TCP socket, sharing the command handling with the unix socket IPC server.
+/// This is opt-in via `--tcp-address` and intended for controlling eww from other devices on trusted
+/// networks: TCP connections are neither authenticated nor encrypted.
+pub async fn run_tcp_server(evt_send: UnboundedSender<app::DaemonCommand>, address: std::net::SocketAddr) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(address).await?;
+    log::info!("TCP remote-control server listening on {}", address);
+    crate::loop_select_exiting! {
+        connection = listener.accept() => match connection {
+            Ok((mut stream, addr)) => {
+                let evt_send = evt_send.clone();
+                tokio::spawn(async move {
+                    log::debug!("Accepted TCP connection from {}", addr);
+                    let (stream_read, stream_write) = stream.split();
+                    let result = handle_stream(stream_read, stream_write, evt_send).await;
+                    crate::print_result_err!("while handling TCP connection with client", result);
+                });
+            },
+            Err(e) => eprintln!("Failed to connect to client: {:?}", e),
+        }
+    }
+    Ok(())
+}
+
+/// Look up the group id for the group given to `--ipc-group`.
+fn lookup_group(name: &str) -> Result<Gid> {
+    let group = nix::unistd::Group::from_name(name).with_context(|| format!("Failed to look up group '{}'", name))?;
+    Ok(group.with_context(|| format!("No group named '{}' exists", name))?.gid)
+}
+
+/// Restrict access to the IPC socket file to the daemon's user, optionally also allowing the given group.
+fn restrict_socket_permissions(socket_path: &Path, allowed_gid: Option<Gid>) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    if let Some(gid) = allowed_gid {
+        nix::unistd::chown(socket_path, None, Some(gid)).context("Failed to change the group of the IPC socket")?;
+    }
+    let mode = if allowed_gid.is_some() { 0o660 } else { 0o600 };
+    std::fs::set_permissions(socket_path, std::fs::Permissions::from_mode(mode))
+        .context("Failed to set permissions on the IPC socket")?;
+    Ok(())
+}
+
 /// Handle a single IPC connection from start to end.
-async fn handle_connection(mut stream: tokio::net::UnixStream, evt_send: UnboundedSender<app::DaemonCommand>) -> Result<()> {
-    let (mut stream_read, mut stream_write) = stream.split();
+async fn handle_connection(
+    mut stream: tokio::net::UnixStream,
+    evt_send: UnboundedSender<app::DaemonCommand>,
+    allowed_gid: Option<Gid>,
+) -> Result<()> {
+    let credentials = stream.peer_cred().context("Failed to query peer credentials of IPC client")?;
+    if !peer_allowed(&credentials, allowed_gid) {
+        bail!("Rejecting IPC connection from unauthorized user (uid {}, gid {})", credentials.uid(), credentials.gid());
+    }
 
+    let (stream_read, stream_write) = stream.split();
+    handle_stream(stream_read, stream_write, evt_send).await
+}
+
+/// Handle a single client connection from start to end, independently of the underlying transport.
+async fn handle_stream(
+    mut stream_read: impl AsyncRead + Unpin,
+    mut stream_write: impl AsyncWrite + Unpin,
+    evt_send: UnboundedSender<app::DaemonCommand>,
+) -> Result<()> {
     let action: opts::ActionWithServer = read_action_from_stream(&mut stream_read).await?;
 
     log::debug!("received command from IPC: {:?}", &action);
 
+    if matches!(action, opts::ActionWithServer::Trace) {
+        return forward_trace_events(&mut stream_read, &mut stream_write).await;
+    }
+
     let (command, maybe_response_recv) = action.into_daemon_command();
 
     evt_send.send(command)?;
@@ -49,9 +118,59 @@ async fn handle_connection(mut stream: tokio::net::UnixStream, evt_send: Unbound
     Ok(())
 }
 
-/// Read a single message from a unix stream, and parses it into a `ActionWithServer`
+/// Forward trace events to an `eww trace` client as newline-delimited text, until it disconnects.
+async fn forward_trace_events(
+    stream_read: &mut (impl AsyncRead + Unpin),
+    stream_write: &mut (impl AsyncWrite + Unpin),
+) -> Result<()> {
+    let (sender, mut recv) = tokio::sync::mpsc::unbounded_channel();
+    crate::tracer::add_listener(sender);
+    log::info!("Client connected to the event trace");
+    let mut disconnect_buf = [0u8; 1];
+    loop {
+        tokio::select! {
+            Some(line) = recv.recv() => {
+                if stream_write.write_all(format!("{}\n", line).as_bytes()).await.is_err() {
+                    break;
+                }
+            }
+            // the client never sends anything after the initial command,
+            // so the read completing means that it disconnected.
+            _ = stream_read.read(&mut disconnect_buf) => break,
+        }
+    }
+    log::info!("Client disconnected from the event trace");
+    Ok(())
+}
+
+/// Check whether a connecting client is allowed to talk to the daemon:
+/// either it runs as the same user as the daemon, or as a member of the group given to `--ipc-group`.
+fn peer_allowed(credentials: &tokio::net::unix::UCred, allowed_gid: Option<Gid>) -> bool {
+    if Uid::from_raw(credentials.uid()) == Uid::effective() {
+        return true;
+    }
+    match allowed_gid {
+        Some(gid) => Gid::from_raw(credentials.gid()) == gid || uid_is_in_group(credentials.uid(), gid),
+        None => false,
+    }
+}
+
+/// Check whether the user with the given uid is a member of the given group.
+fn uid_is_in_group(uid: u32, gid: Gid) -> bool {
+    let user = match nix::unistd::User::from_uid(Uid::from_raw(uid)) {
+        Ok(Some(user)) => user,
+        _ => return false,
+    };
+    let user_name = match std::ffi::CString::new(user.name) {
+        Ok(user_name) => user_name,
+        Err(_) => return false,
+    };
+    nix::unistd::getgrouplist(&user_name, user.gid).map_or(false, |groups| groups.contains(&gid))
+}
+
+/// Read a single message from a stream, and parses it into a `ActionWithServer`
 /// The format here requires the first 4 bytes to be the size of the rest of the message (in big-endian), followed by the rest of the message.
-async fn read_action_from_stream(stream_read: &'_ mut tokio::net::unix::ReadHalf<'_>) -> Result<opts::ActionWithServer> {
+async fn read_action_from_stream(stream_read: &mut (impl AsyncRead + Unpin)) -> Result<opts::ActionWithServer> {
     let mut message_byte_length = [0u8; 4];
     stream_read.read_exact(&mut message_byte_length).await.context("Failed to read message size header in IPC message")?;
     let message_byte_length = u32::from_be_bytes(message_byte_length);
@@ -31,9 +31,11 @@ mod geometry;
 mod ipc_server;
 mod opts;
 mod paths;
+mod profiler;
 mod script_var_handler;
 mod server;
 mod state;
+mod tracer;
 mod util;
 mod widgets;
 
@@ -121,7 +123,14 @@ fn run<B: DisplayBackend>(opts: opts::Opt, eww_binary_name: String, display_back
             if !opts.show_logs {
                 println!("Run `{} logs` to see any errors while editing your configuration.", eww_binary_name);
             }
-            let fork_result = server::initialize_server(paths.clone(), None, display_backend, !opts.no_daemonize)?;
+            let fork_result = server::initialize_server(
+                paths.clone(),
+                None,
+                display_backend,
+                !opts.no_daemonize,
+                opts.ipc_group.clone(),
+                opts.tcp_address,
+            )?;
             opts.no_daemonize || fork_result == ForkResult::Parent
         }
 
@@ -132,6 +141,13 @@ fn run<B: DisplayBackend>(opts: opts::Opt, eww_binary_name: String, display_back
             false
         }
 
+        // trace streams events over a long-lived connection instead of the usual single-response call
+        opts::Action::WithServer(ActionWithServer::Trace) => {
+            let mut stream = attempt_connect(paths.get_ipc_socket_file(), 5).context("Failed to connect to daemon")?;
+            client::handle_trace(&mut stream)?;
+            false
+        }
+
         // a running daemon is necessary for this command
         opts::Action::WithServer(action) => {
             // attempt to just send the command to a running daemon
@@ -153,7 +169,14 @@ fn run<B: DisplayBackend>(opts: opts::Opt, eww_binary_name: String, display_back
 
                     let (command, response_recv) = action.into_daemon_command();
                     // start the daemon and give it the command
-                    let fork_result = server::initialize_server(paths.clone(), Some(command), display_backend, true)?;
+                    let fork_result = server::initialize_server(
+                        paths.clone(),
+                        Some(command),
+                        display_backend,
+                        true,
+                        opts.ipc_group.clone(),
+                        opts.tcp_address,
+                    )?;
                     let is_parent = fork_result == ForkResult::Parent;
                     if let (Some(recv), true) = (response_recv, is_parent) {
                         listen_for_daemon_response(recv);
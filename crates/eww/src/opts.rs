@@ -23,6 +23,8 @@ pub struct Opt {
     pub config_path: Option<std::path::PathBuf>,
     pub action: Action,
     pub no_daemonize: bool,
+    pub ipc_group: Option<String>,
+    pub tcp_address: Option<std::net::SocketAddr>,
 }
 
 #[derive(Parser, Debug, Serialize, Deserialize, PartialEq)]
@@ -53,6 +55,18 @@ struct RawOpt {
     #[arg(long = "restart", global = true)]
     restart: bool,
 
+    /// Allow members of the given group to connect to the daemon's IPC socket.
+    /// By default, only the user running the daemon may connect.
+    #[arg(long = "ipc-group", global = true)]
+    ipc_group: Option<String>,
+
+    /// Additionally listen for commands on the given TCP address (e.g. `0.0.0.0:4278`),
+    /// allowing eww to be controlled from other devices on the network.
+    /// WARNING: TCP connections are neither authenticated nor encrypted,
+    /// so this should only be used on trusted networks.
+    #[arg(long = "tcp-address", global = true)]
+    tcp_address: Option<std::net::SocketAddr>,
+
     #[command(subcommand)]
     action: Action,
 }
@@ -179,6 +193,41 @@ pub enum ActionWithServer {
     /// Print out the scope graph structure in graphviz dot format.
     #[command(name = "graph")]
     ShowGraph,
+
+    /// Print the time spent on variable resolution and widget updates since the last call,
+    /// sorted by total time spent. Useful to find the expression or widget slowing down your config.
+    /// The first call only enables the collection of timing data.
+    #[command(name = "profile")]
+    ShowProfile,
+
+    /// Print statistics about the running daemon: open windows, scope graph size and memory usage.
+    #[command(name = "stats")]
+    ShowStats,
+
+    /// Store transient key-value data in the daemon, shared between scripts and expressions.
+    /// The current store content is accessible in expressions via the `EWW_STORE` json object.
+    #[command(name = "store", subcommand)]
+    Store(StoreCommand),
+
+    /// Stream a timestamped trace of every command, variable update and window action
+    /// the daemon performs, until interrupted. Useful to correlate script output with UI behavior.
+    #[command(name = "trace")]
+    Trace,
+}
+
+#[derive(Subcommand, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum StoreCommand {
+    /// Set the value stored under the given key
+    #[command(name = "set")]
+    Set { key: String, value: String },
+
+    /// Print the value stored under the given key
+    #[command(name = "get")]
+    Get { key: String },
+
+    /// Delete the value stored under the given key
+    #[command(name = "del")]
+    Del { key: String },
 }
 
 impl Opt {
@@ -190,8 +239,8 @@ impl Opt {
 
 impl From<RawOpt> for Opt {
     fn from(other: RawOpt) -> Self {
-        let RawOpt { log_debug, force_wayland, config, show_logs, no_daemonize, restart, action } = other;
-        Opt { log_debug, force_wayland, show_logs, restart, config_path: config, action, no_daemonize }
+        let RawOpt { log_debug, force_wayland, config, show_logs, no_daemonize, restart, ipc_group, tcp_address, action } = other;
+        Opt { log_debug, force_wayland, show_logs, restart, config_path: config, action, no_daemonize, ipc_group, tcp_address }
     }
 }
 
@@ -247,6 +296,20 @@ impl ActionWithServer {
             }
             ActionWithServer::ShowDebug => return with_response_channel(app::DaemonCommand::PrintDebug),
             ActionWithServer::ShowGraph => return with_response_channel(app::DaemonCommand::PrintGraph),
+            ActionWithServer::ShowProfile => return with_response_channel(app::DaemonCommand::PrintProfile),
+            ActionWithServer::ShowStats => return with_response_channel(app::DaemonCommand::PrintStats),
+            ActionWithServer::Store(store_command) => {
+                return with_response_channel(|sender| match store_command {
+                    StoreCommand::Set { key, value } => {
+                        app::DaemonCommand::StoreSet { key, value: DynVal::from_string(value), sender }
+                    }
+                    StoreCommand::Get { key } => app::DaemonCommand::StoreGet { key, sender },
+                    StoreCommand::Del { key } => app::DaemonCommand::StoreDel { key, sender },
+                });
+            }
+            // `eww trace` is handled by a dedicated streaming connection (see `client::handle_trace`)
+            // and never goes through the regular command path.
+            ActionWithServer::Trace => app::DaemonCommand::NoOp,
         };
         (command, None)
     }
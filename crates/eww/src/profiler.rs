@@ -0,0 +1,82 @@
+//! Lightweight collection of timing information about variable resolution
+//! and widget-attribute updates. The collected stats can be printed (and reset) via `eww profile`,
+//! allowing users to find the expression or widget that is slowing down their configuration.
+//! Collection only starts with the first `eww profile` call, so that the recording overhead
+//! is not paid while the profiler is never used.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use itertools::Itertools;
+use once_cell::sync::Lazy;
+
+static PROFILER: Lazy<Mutex<HashMap<String, TimingStats>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+static PROFILING_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Check whether profiling data is currently being collected.
+pub fn is_active() -> bool {
+    PROFILING_ENABLED.load(Ordering::Relaxed)
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct TimingStats {
+    count: u64,
+    total: Duration,
+    max: Duration,
+}
+
+/// Record that a single run of the given entry took the given duration.
+pub fn record(name: String, duration: Duration) {
+    let mut profiler = PROFILER.lock().unwrap();
+    let stats = profiler.entry(name).or_default();
+    stats.count += 1;
+    stats.total += duration;
+    stats.max = stats.max.max(duration);
+}
+
+/// Run the given function, recording the time it took under the entry name produced by `name`.
+/// While profiling is not active, the function simply runs without any recording overhead.
+pub fn measure<T>(name: impl FnOnce() -> String, f: impl FnOnce() -> T) -> T {
+    if !is_active() {
+        return f();
+    }
+    let start = Instant::now();
+    let result = f();
+    record(name(), start.elapsed());
+    result
+}
+
+/// Format the stats collected since the last call to this function,
+/// sorted by the total time spent, and reset the collection.
+/// The first call only enables the collection of timing data.
+pub fn take_report() -> String {
+    if !PROFILING_ENABLED.swap(true, Ordering::Relaxed) {
+        return "Profiling is now enabled. Run `eww profile` again to see the timings collected from now on.".to_string();
+    }
+    let mut profiler = PROFILER.lock().unwrap();
+    if profiler.is_empty() {
+        return "No profiling data has been collected since the last report".to_string();
+    }
+    let mut entries: Vec<_> = profiler.drain().collect();
+    entries.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.total));
+    entries
+        .iter()
+        .map(|(name, stats)| {
+            format!(
+                "{:>12.2?} total | {:>12.2?} avg | {:>12.2?} max | {:>6} runs | {}",
+                stats.total,
+                stats.total / stats.count as u32,
+                stats.max,
+                stats.count,
+                name
+            )
+        })
+        .join("\n")
+}
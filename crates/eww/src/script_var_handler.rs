@@ -193,12 +193,15 @@ impl PollVarHandler {
 }
 
 fn run_poll_once(var: &PollScriptVar) -> Result<DynVal> {
-    match &var.command {
+    crate::profiler::measure(|| format!("resolution of poll variable `{}`", var.name), || match &var.command {
         VarSource::Shell(span, command) => {
-            script_var::run_command(command).map_err(|e| anyhow!(create_script_var_failed_warn(*span, &var.name, &e.to_string())))
+            let value = script_var::run_command(command)
+                .map_err(|e| anyhow!(create_script_var_failed_warn(*span, &var.name, &e.to_string())))?;
+            script_var::validate_output_format(*span, &var.name, var.format, &value)?;
+            Ok(value)
         }
         VarSource::Function(x) => x().map_err(|e| anyhow!(e)),
-    }
+    })
 }
 
 impl Drop for PollVarHandler {
@@ -256,7 +259,10 @@ impl ListenVarHandler {
                     }
                     Ok(Some(line)) = stdout_lines.next_line() => {
                         let new_value = DynVal::from_string(line.to_owned());
-                        evt_send.send(DaemonCommand::UpdateVars(vec![(var.name.to_owned(), new_value)]))?;
+                        match script_var::validate_output_format(var.command_span, &var.name, var.format, &new_value) {
+                            Ok(()) => evt_send.send(DaemonCommand::UpdateVars(vec![(var.name.to_owned(), new_value)]))?,
+                            Err(err) => crate::error_handling_ctx::print_error(err),
+                        }
                     }
                     Ok(Some(line)) = stderr_lines.next_line() => {
                         log::warn!("stderr of `{}`: {}", var.name, line);
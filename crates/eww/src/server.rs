@@ -23,6 +23,8 @@ pub fn initialize_server<B: DisplayBackend>(
     action: Option<DaemonCommand>,
     display_backend: B,
     should_daemonize: bool,
+    ipc_group: Option<String>,
+    tcp_address: Option<std::net::SocketAddr>,
 ) -> Result<ForkResult> {
     let (ui_send, mut ui_recv) = tokio::sync::mpsc::unbounded_channel();
 
@@ -36,8 +38,14 @@ pub fn initialize_server<B: DisplayBackend>(
     let eww_config = match read_config {
         Ok(config) => config,
         Err(err) => {
+            let error_message = error_handling_ctx::format_error_plain(&err);
             error_handling_ctx::print_error(err);
-            config::EwwConfig::default()
+            log::warn!("Starting in safe mode. Fix your configuration and run `eww reload` to exit safe mode.");
+            config::EwwConfig::generate_safe_mode(&mut error_handling_ctx::FILE_DATABASE.write().unwrap(), error_message)
+                .unwrap_or_else(|err| {
+                    log::error!("Failed to generate safe mode configuration: {:?}", err);
+                    config::EwwConfig::default()
+                })
         }
     };
 
@@ -81,6 +89,7 @@ pub fn initialize_server<B: DisplayBackend>(
         eww_config,
         open_windows: HashMap::new(),
         failed_windows: HashSet::new(),
+        store: HashMap::new(),
         css_provider: gtk::CssProvider::new(),
         script_var_handler,
         app_evt_send: ui_send.clone(),
@@ -99,9 +108,20 @@ pub fn initialize_server<B: DisplayBackend>(
     }
 
     // initialize all the handlers and tasks running asyncronously
-    init_async_part(app.paths.clone(), ui_send);
+    init_async_part(app.paths.clone(), ui_send, ipc_group, tcp_address);
 
     glib::MainContext::default().spawn_local(async move {
+        // open all windows that are marked as open-by-default in the configuration
+        let default_windows: Vec<String> =
+            app.eww_config.get_windows().values().filter(|def| def.open_by_default).map(|def| def.name.clone()).collect();
+        if !default_windows.is_empty() {
+            let (sender, mut response_recv) = daemon_response::create_pair();
+            app.handle_command(app::DaemonCommand::OpenMany { windows: default_windows, should_toggle: false, sender });
+            if let Ok(daemon_response::DaemonResponse::Failure(e)) = response_recv.try_recv() {
+                log::error!("Error opening default windows: {}", e);
+            }
+        }
+
         // if an action was given to the daemon initially, execute it first.
         if let Some(action) = action {
             app.handle_command(action);
@@ -126,7 +146,12 @@ pub fn initialize_server<B: DisplayBackend>(
     Ok(ForkResult::Child)
 }
 
-fn init_async_part(paths: EwwPaths, ui_send: UnboundedSender<app::DaemonCommand>) {
+fn init_async_part(
+    paths: EwwPaths,
+    ui_send: UnboundedSender<app::DaemonCommand>,
+    ipc_group: Option<String>,
+    tcp_address: Option<std::net::SocketAddr>,
+) {
     std::thread::Builder::new()
         .name("outer-main-async-runtime".to_string())
         .spawn(move || {
@@ -145,7 +170,17 @@ fn init_async_part(paths: EwwPaths, ui_send: UnboundedSender<app::DaemonCommand>
 
                 let ipc_server_join_handle = {
                     let ui_send = ui_send.clone();
-                    tokio::spawn(async move { ipc_server::run_server(ui_send, paths.get_ipc_socket_file()).await })
+                    tokio::spawn(async move { ipc_server::run_server(ui_send, paths.get_ipc_socket_file(), ipc_group).await })
+                };
+
+                let tcp_server_join_handle = {
+                    let ui_send = ui_send.clone();
+                    tokio::spawn(async move {
+                        match tcp_address {
+                            Some(address) => ipc_server::run_tcp_server(ui_send, address).await,
+                            None => Ok(()),
+                        }
+                    })
                 };
 
                 let forward_exit_to_app_handle = {
@@ -159,7 +194,8 @@ fn init_async_part(paths: EwwPaths, ui_send: UnboundedSender<app::DaemonCommand>
                     })
                 };
 
-                let result = tokio::try_join!(filewatch_join_handle, ipc_server_join_handle, forward_exit_to_app_handle);
+                let result =
+                    tokio::try_join!(filewatch_join_handle, ipc_server_join_handle, tcp_server_join_handle, forward_exit_to_app_handle);
 
                 if let Err(e) = result {
                     log::error!("Eww exiting with error: {:?}", e);
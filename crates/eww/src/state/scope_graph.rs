@@ -110,6 +110,10 @@ impl ScopeGraph {
         self.graph.remove_scope(scope_index);
     }
 
+    pub fn scope_count(&self) -> usize {
+        self.graph.scope_count()
+    }
+
     pub fn validate(&self) -> Result<()> {
         self.graph.validate()
     }
@@ -290,9 +294,12 @@ impl ScopeGraph {
     fn call_listeners_in_scope(&mut self, scope_index: ScopeIndex, updated_var: &VarName) -> Result<()> {
         let scope = self.graph.scope_at(scope_index).context("Scope not in graph")?;
         if let Some(triggered_listeners) = scope.listeners.get(updated_var) {
+            let scope_name = scope.name.clone();
             for listener in triggered_listeners.clone() {
                 let required_variables = self.lookup_variables_in_scope(scope_index, &listener.needed_variables)?;
-                if let Err(err) = (*listener.f)(self, required_variables).context("Error while updating UI after state change") {
+                let call_listener = || (*listener.f)(self, required_variables).context("Error while updating UI after state change");
+                let profiler_key = || format!("widget updates in scope `{}` triggered by `{}`", scope_name, updated_var);
+                if let Err(err) = crate::profiler::measure(profiler_key, call_listener) {
                     error_handling_ctx::print_error(err);
                 }
             }
@@ -446,6 +453,10 @@ mod internal {
             self.inheritance_relations.remove(index);
         }
 
+        pub fn scope_count(&self) -> usize {
+            self.scopes.len()
+        }
+
         pub fn add_inheritance_relation(&mut self, a: ScopeIndex, b: ScopeIndex) {
             self.inheritance_relations.insert(a, b, Inherits { references: HashSet::new() }).unwrap();
         }
@@ -0,0 +1,33 @@
+//! Diagnostic event stream behind `eww trace`.
+//! While at least one trace client is connected, every daemon command, variable update
+//! and window action is timestamped and forwarded to the connected clients,
+//! allowing users to correlate script output with UI behavior.
+
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use tokio::sync::mpsc::UnboundedSender;
+
+static TRACE_LISTENERS: Lazy<Mutex<Vec<UnboundedSender<String>>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Register a new trace client. Every trace event will be sent to the given sender
+/// until the receiving side is dropped.
+pub fn add_listener(sender: UnboundedSender<String>) {
+    TRACE_LISTENERS.lock().unwrap().push(sender);
+}
+
+/// Check whether any trace client is currently connected.
+/// Use this to avoid formatting event messages while nobody is listening.
+pub fn is_active() -> bool {
+    !TRACE_LISTENERS.lock().unwrap().is_empty()
+}
+
+/// Send a timestamped event message to all connected trace clients.
+pub fn emit(message: String) {
+    let mut listeners = TRACE_LISTENERS.lock().unwrap();
+    if listeners.is_empty() {
+        return;
+    }
+    let line = format!("[{}] {}", chrono::Local::now().format("%H:%M:%S%.3f"), message);
+    listeners.retain(|listener| listener.send(line.clone()).is_ok());
+}
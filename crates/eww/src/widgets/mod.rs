@@ -39,6 +39,29 @@ where
         .expect("Failed to start command-execution-thread");
 }
 
+/// Run a long-running command (e.g. a plugin process) that was provided as an attribute,
+/// without applying any timeout. Placeholders are replaced like in [`run_command`].
+#[cfg(feature = "x11")]
+pub(self) fn spawn_command<T>(cmd: &str, args: &[T])
+where
+    T: 'static + std::fmt::Display + Send + Sync + Clone,
+{
+    let cmd = replace_placeholders(cmd, args);
+    std::thread::Builder::new()
+        .name("plugin-command-thread".to_string())
+        .spawn(move || {
+            log::debug!("Spawning long-running command from widget: {}", cmd);
+            match Command::new("/bin/sh").arg("-c").arg(&cmd).spawn() {
+                // wait for the child to avoid leaving a zombie process behind when it exits
+                Ok(mut child) => {
+                    let _ = child.wait();
+                }
+                Err(err) => log::error!("Failed to launch child process: {}", err),
+            }
+        })
+        .expect("Failed to start plugin-command-thread");
+}
+
 fn replace_placeholders<T>(cmd: &str, args: &[T]) -> String
 where
     T: 'static + std::fmt::Display + Send + Sync + Clone,
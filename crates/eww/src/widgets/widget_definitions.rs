@@ -80,6 +80,7 @@ pub const BUILTIN_WIDGET_NAMES: &[&str] = &[
     WIDGET_NAME_REVEALER,
     WIDGET_NAME_SCROLL,
     WIDGET_NAME_OVERLAY,
+    WIDGET_NAME_PLUGIN,
 ];
 
 //// widget definitions
@@ -107,6 +108,19 @@ pub(super) fn widget_use_to_gtk_widget(bargs: &mut BuilderArgs) -> Result<gtk::W
         WIDGET_NAME_REVEALER => build_gtk_revealer(bargs)?.upcast(),
         WIDGET_NAME_SCROLL => build_gtk_scrolledwindow(bargs)?.upcast(),
         WIDGET_NAME_OVERLAY => build_gtk_overlay(bargs)?.upcast(),
+        #[cfg(feature = "x11")]
+        WIDGET_NAME_PLUGIN if gdk::Display::default().map_or(false, |display| display.backend().is_x11()) => {
+            build_gtk_plugin(bargs)?.upcast()
+        }
+        // XEmbed only exists on x11, so the widget is also rejected when an x11-enabled build
+        // runs on a wayland display - realizing a socket there would abort inside GTK.
+        WIDGET_NAME_PLUGIN => {
+            return Err(DiagError(gen_diagnostic! {
+                msg = "the plugin widget requires eww to be running on x11",
+                label = bargs.widget_use.name_span => "Used here",
+            })
+            .into())
+        }
         _ => {
             return Err(DiagError(gen_diagnostic! {
                 msg = format!("referenced unknown widget `{}`", bargs.widget_use.name),
@@ -971,6 +985,45 @@ fn build_gtk_calendar(bargs: &mut BuilderArgs) -> Result<gtk::Calendar> {
     Ok(gtk_widget)
 }
 
+const WIDGET_NAME_PLUGIN: &str = "plugin";
+/// @widget plugin
+/// @desc A widget provided by an external plugin process, embedded via the XEmbed protocol (X11 only).
+/// The plugin process is expected to embed its UI (using e.g. GtkPlug) into the X11 window
+/// whose id is given to it through the command placeholder.
+#[cfg(feature = "x11")]
+fn build_gtk_plugin(bargs: &mut BuilderArgs) -> Result<gtk::Socket> {
+    let gtk_widget = gtk::Socket::new();
+    // keep the socket widget alive when the plugin process detaches, so another plug may embed later.
+    gtk_widget.connect_plug_removed(|_| {
+        log::warn!("Plugin process detached from its socket");
+        true
+    });
+    let spawned = Rc::new(RefCell::new(false));
+    def_widget!(bargs, _g, gtk_widget, {
+        // @prop command - the command spawning the plugin process. The `{}` placeholder will be replaced
+        // by the id of the X11 window the plugin must embed its UI into.
+        prop(command: as_string) {
+            // The process is only spawned once per socket: the prop is re-evaluated whenever
+            // a variable used in the command changes, and spawning again would pile up plugin
+            // processes all trying to embed into the same socket.
+            if *spawned.borrow() {
+                log::warn!("Ignoring changed command of an already running plugin widget");
+            } else {
+                *spawned.borrow_mut() = true;
+                if gtk_widget.is_realized() {
+                    super::spawn_command(&command, &[gtk_widget.id()]);
+                } else {
+                    // the socket only has a window id to embed into once it is realized
+                    connect_signal_handler!(gtk_widget, gtk_widget.connect_realize(move |socket| {
+                        super::spawn_command(&command, &[socket.id()]);
+                    }));
+                }
+            }
+        },
+    });
+    Ok(gtk_widget)
+}
+
 const WIDGET_NAME_TRANSFORM: &str = "transform";
 /// @widget transform
 /// @desc A widget that applies transformations to its content. They are applied in the following
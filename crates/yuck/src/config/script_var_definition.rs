@@ -1,12 +1,15 @@
 use simplexpr::{dynval::DynVal, SimplExpr};
 
 use crate::{
+    enum_parse,
     error::{DiagError, DiagResult, DiagResultExt},
     format_diagnostic::ToDiagnostic,
     parser::{ast::Ast, ast_iterator::AstIterator, from_ast::FromAstElementContent},
 };
 use eww_shared_util::{Span, VarName};
 
+use super::window_definition::EnumParseError;
+
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
 pub enum ScriptVarDefinition {
     Poll(PollScriptVar),
@@ -47,6 +50,33 @@ pub enum VarSource {
     Function(fn() -> Result<DynVal, Box<dyn std::error::Error + Sync + Send + 'static>>),
 }
 
+/// Expected output format of a script variable, declared via the `:as` attribute.
+/// Output that does not match the declared format is rejected at ingestion,
+/// instead of failing later inside the widget expressions using the variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display, smart_default::SmartDefault, serde::Serialize)]
+pub enum ScriptVarFormat {
+    /// Use the output as-is, without any validation
+    #[default]
+    #[display(fmt = "auto")]
+    Auto,
+    #[display(fmt = "json")]
+    Json,
+    #[display(fmt = "number")]
+    Number,
+}
+
+impl std::str::FromStr for ScriptVarFormat {
+    type Err = EnumParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        enum_parse! { "output format", s,
+            "auto" => ScriptVarFormat::Auto,
+            "json" => ScriptVarFormat::Json,
+            "number" => ScriptVarFormat::Number,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
 pub struct PollScriptVar {
     pub name: VarName,
@@ -54,6 +84,8 @@ pub struct PollScriptVar {
     pub command: VarSource,
     pub initial_value: Option<DynVal>,
     pub interval: std::time::Duration,
+    /// Expected output format of the command, validated at ingestion
+    pub format: ScriptVarFormat,
     pub name_span: Span,
 }
 
@@ -67,6 +99,7 @@ impl FromAstElementContent for PollScriptVar {
             let initial_value = Some(attrs.primitive_optional("initial")?.unwrap_or_else(|| DynVal::from_string(String::new())));
             let interval =
                 attrs.primitive_required::<DynVal, _>("interval")?.as_duration().map_err(|e| DiagError(e.to_diagnostic()))?;
+            let format = attrs.primitive_optional("as")?.unwrap_or(ScriptVarFormat::Auto);
             let (script_span, script) = iter.expect_literal()?;
 
             let run_while_expr =
@@ -80,6 +113,7 @@ impl FromAstElementContent for PollScriptVar {
                 command: VarSource::Shell(script_span, script.to_string()),
                 initial_value,
                 interval,
+                format,
             }
         };
         result.note(r#"Expected format: `(defpoll name :interval "10s" "echo 'a shell script'")`"#)
@@ -91,6 +125,8 @@ pub struct ListenScriptVar {
     pub name: VarName,
     pub command: String,
     pub initial_value: DynVal,
+    /// Expected format of each output line, validated at ingestion
+    pub format: ScriptVarFormat,
     pub command_span: Span,
     pub name_span: Span,
 }
@@ -102,10 +138,24 @@ impl FromAstElementContent for ListenScriptVar {
             let (name_span, name) = iter.expect_symbol()?;
             let mut attrs = iter.expect_key_values()?;
             let initial_value = attrs.primitive_optional("initial")?.unwrap_or_else(|| DynVal::from_string(String::new()));
+            let format = attrs.primitive_optional("as")?.unwrap_or(ScriptVarFormat::Auto);
             let (command_span, script) = iter.expect_literal()?;
             iter.expect_done()?;
-            Self { name_span, name: VarName(name), command: script.to_string(), initial_value, command_span }
+            Self { name_span, name: VarName(name), command: script.to_string(), initial_value, format, command_span }
         };
         result.note(r#"Expected format: `(deflisten name :initial "0" "tail -f /tmp/example")`"#)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_script_var_format() {
+        assert_eq!("auto".parse::<ScriptVarFormat>().unwrap(), ScriptVarFormat::Auto);
+        assert_eq!("json".parse::<ScriptVarFormat>().unwrap(), ScriptVarFormat::Json);
+        assert_eq!("number".parse::<ScriptVarFormat>().unwrap(), ScriptVarFormat::Number);
+        assert!("yaml".parse::<ScriptVarFormat>().is_err());
+    }
+}
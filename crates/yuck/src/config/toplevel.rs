@@ -90,11 +90,22 @@ pub struct Config {
     pub window_definitions: HashMap<String, WindowDefinition>,
     pub var_definitions: HashMap<VarName, VarDefinition>,
     pub script_vars: HashMap<VarName, ScriptVarDefinition>,
+    /// Hash of the pretty-printed AST of every toplevel definition, keyed by `(element name, definition name)`.
+    /// The printed form contains no source positions, so definitions that merely got shifted around
+    /// by edits elsewhere in the file keep their hash. Used to detect which definitions actually
+    /// changed across a config reload.
+    pub content_hashes: HashMap<(&'static str, String), u64>,
 }
 
 impl Config {
-    fn append_toplevel(&mut self, files: &mut impl YuckFileProvider, toplevel: TopLevel) -> DiagResult<()> {
-        match toplevel {
+    fn append_toplevel(&mut self, files: &mut impl YuckFileProvider, element: Ast) -> DiagResult<()> {
+        let content_hash = {
+            use std::hash::{Hash, Hasher};
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            element.to_string().hash(&mut hasher);
+            hasher.finish()
+        };
+        match TopLevel::from_ast(element)? {
             TopLevel::VarDefinition(x) => {
                 if self.var_definitions.contains_key(&x.name) || self.script_vars.contains_key(&x.name) {
                     return Err(DiagError(gen_diagnostic! {
@@ -102,6 +113,7 @@ impl Config {
                         label = x.span => "defined again here",
                     }));
                 } else {
+                    self.content_hashes.insert((VarDefinition::ELEMENT_NAME, x.name.to_string()), content_hash);
                     self.var_definitions.insert(x.name.clone(), x);
                 }
             }
@@ -112,13 +124,20 @@ impl Config {
                         label = x.name_span() => "defined again here",
                     }));
                 } else {
+                    let element_name = match &x {
+                        ScriptVarDefinition::Poll(_) => PollScriptVar::ELEMENT_NAME,
+                        ScriptVarDefinition::Listen(_) => ListenScriptVar::ELEMENT_NAME,
+                    };
+                    self.content_hashes.insert((element_name, x.name().to_string()), content_hash);
                     self.script_vars.insert(x.name().clone(), x);
                 }
             }
             TopLevel::WidgetDefinition(x) => {
+                self.content_hashes.insert((WidgetDefinition::ELEMENT_NAME, x.name.clone()), content_hash);
                 self.widget_definitions.insert(x.name.clone(), x);
             }
             TopLevel::WindowDefinition(x) => {
+                self.content_hashes.insert((WindowDefinition::ELEMENT_NAME, x.name.clone()), content_hash);
                 self.window_definitions.insert(x.name.clone(), x);
             }
             TopLevel::Include(include) => {
@@ -130,7 +149,7 @@ impl Config {
                     FilesError::DiagError(x) => x,
                 })?;
                 for element in toplevels {
-                    self.append_toplevel(files, TopLevel::from_ast(element)?)?;
+                    self.append_toplevel(files, element)?;
                 }
             }
         }
@@ -143,9 +162,10 @@ impl Config {
             window_definitions: HashMap::new(),
             var_definitions: HashMap::new(),
             script_vars: HashMap::new(),
+            content_hashes: HashMap::new(),
         };
         for element in elements {
-            config.append_toplevel(files, TopLevel::from_ast(element)?)?;
+            config.append_toplevel(files, element)?;
         }
         Ok(config)
     }
@@ -158,3 +178,55 @@ impl Config {
         Self::generate(files, top_levels)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parser;
+
+    /// Provider that only supports string input, as the test configurations don't use `include`.
+    struct TestFileProvider;
+    impl YuckFileProvider for TestFileProvider {
+        fn load_yuck_file(&mut self, _path: std::path::PathBuf) -> Result<(Span, Vec<Ast>), FilesError> {
+            unimplemented!("loading files is not supported in tests")
+        }
+
+        fn load_yuck_str(&mut self, _name: String, content: String) -> Result<(Span, Vec<Ast>), DiagError> {
+            parser::parse_toplevel(0, content)
+        }
+
+        fn unload(&mut self, _id: usize) {}
+    }
+
+    fn content_hashes(config: &str) -> HashMap<(&'static str, String), u64> {
+        let mut files = TestFileProvider;
+        let (_, asts) = files.load_yuck_str("test.yuck".to_string(), config.to_string()).unwrap();
+        Config::generate(&mut files, asts).unwrap().content_hashes
+    }
+
+    #[test]
+    fn test_content_hashes_ignore_spans() {
+        let compact = content_hashes(r#"(defvar foo "bar")(defwindow w :geometry (geometry :x "0%") (label :text foo))"#);
+        let shifted = content_hashes(
+            r#"
+            ; comments and whitespace shift all spans around
+            (defvar foo "bar")
+
+            (defwindow w
+              :geometry (geometry :x "0%")
+              (label :text foo))
+            "#,
+        );
+        assert_eq!(compact, shifted);
+    }
+
+    #[test]
+    fn test_content_hashes_detect_changes() {
+        let original = content_hashes(r#"(defvar foo "bar")(defwindow w (label :text foo))"#);
+        let changed = content_hashes(r#"(defvar foo "bar")(defwindow w (label :text "changed"))"#);
+        let window_key = (WindowDefinition::ELEMENT_NAME, "w".to_string());
+        let var_key = (VarDefinition::ELEMENT_NAME, "foo".to_string());
+        assert_ne!(original.get(&window_key), changed.get(&window_key));
+        assert_eq!(original.get(&var_key), changed.get(&var_key));
+    }
+}
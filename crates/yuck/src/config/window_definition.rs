@@ -21,6 +21,17 @@ pub struct WindowDefinition {
     pub monitor: Option<MonitorIdentifier>,
     pub widget: WidgetUse,
     pub resizable: bool,
+    pub grab: bool,
+    /// Whether the window covers the whole monitor on the background layer, ignoring all input
+    pub wallpaper: bool,
+    /// Name of another window that this window's geometry is computed relative to
+    pub relative_to: Option<String>,
+    /// Whether the window's actual geometry should be captured and restored when the config is reloaded
+    pub preserve_geometry: bool,
+    /// Whether the window should be opened automatically when the daemon starts
+    pub open_by_default: bool,
+    /// Whether anchored positions should account for space reserved by other panels
+    pub respect_workarea: bool,
     pub backend_options: BackendWindowOptions,
 }
 
@@ -33,11 +44,31 @@ impl FromAstElementContent for WindowDefinition {
         let monitor = attrs.primitive_optional("monitor")?;
         let resizable = attrs.primitive_optional("resizable")?.unwrap_or(true);
         let stacking = attrs.primitive_optional("stacking")?.unwrap_or(WindowStacking::Foreground);
+        let grab = attrs.primitive_optional("grab")?.unwrap_or(false);
+        let wallpaper = attrs.primitive_optional("wallpaper")?.unwrap_or(false);
+        let relative_to = attrs.primitive_optional("relative-to")?;
+        let preserve_geometry = attrs.primitive_optional("preserve-geometry")?.unwrap_or(false);
+        let open_by_default = attrs.primitive_optional("open-by-default")?.unwrap_or(false);
+        let respect_workarea = attrs.primitive_optional("respect-workarea")?.unwrap_or(false);
         let geometry = attrs.ast_optional("geometry")?;
         let backend_options = BackendWindowOptions::from_attrs(&mut attrs)?;
         let widget = iter.expect_any().map_err(DiagError::from).and_then(WidgetUse::from_ast)?;
         iter.expect_done()?;
-        Ok(Self { name, monitor, resizable, widget, stacking, geometry, backend_options })
+        Ok(Self {
+            name,
+            monitor,
+            resizable,
+            widget,
+            stacking,
+            geometry,
+            grab,
+            wallpaper,
+            relative_to,
+            preserve_geometry,
+            open_by_default,
+            respect_workarea,
+            backend_options,
+        })
     }
 }
 